@@ -1,6 +1,6 @@
 use dirs::home_dir;
 use regex::Regex;
-use std::process::Command;
+use std::rc::Rc;
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -15,6 +15,9 @@ enum AppMsg {
     SearchChanged(String),
     MimeSelected(u32),
     AppSet(String),
+    FileDropped(PathBuf),
+    PaletteSearch(String),
+    PaletteMimeSelected(usize),
 }
 
 /// The mode of interaction
@@ -33,13 +36,18 @@ struct AppModel {
     selected_mime: String,                  // the selected mime
     current_list: gtk::StringList,          // the current items in the current dropdown
     mode: AppMode,                          // is the user searching? or selecting? or setting?
+    palette_query: String,                  // the query typed into the command palette
+    notice: Option<String>,                 // a one-off status message (e.g. an unhandled drop)
 }
 
 /// The widgets
 struct AppWidgets {
-    label: gtk::Label,       // the label that updates when searching
-    dropdown: gtk::DropDown, // the dropdown containing [narrowed-down] list of mimes
-    appbox: gtk::Box,        // the Box for listing apps for the selected mime
+    label: gtk::Label,              // the label that updates when searching
+    dropdown: gtk::DropDown,        // the dropdown containing [narrowed-down] list of mimes
+    appbox: gtk::Box,               // the Box for listing apps for the selected mime
+    palette: gtk::Popover,          // the Ctrl+P command palette overlay
+    palette_search: gtk::SearchEntry, // the palette's own search entry
+    palette_list: gtk::ListBox,     // the ranked palette entries
 }
 
 /// Visualize the variables using Component trait
@@ -73,11 +81,12 @@ impl Component for AppModel {
         window: Self::Root,
         sender: ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
-        let mimes_apps = get_mimes_apps(mime_paths);
+        let mimes_apps = get_mimes_apps_cached(mime_paths);
         let selected_mime_index = 0;
         let selected_mime = String::from("");
         let mode = AppMode::Searching; // the default mode
         let search_str = String::from("");
+        let palette_query = String::from("");
 
         // a trick to make the dropdown look unselected
         let mut mimes_list = vec!["Select a mime ..."];
@@ -93,6 +102,8 @@ impl Component for AppModel {
             selected_mime_index,
             selected_mime,
             current_list,
+            palette_query,
+            notice: None,
         };
 
         let search_entry = gtk::SearchEntry::new();
@@ -112,6 +123,132 @@ impl Component for AppModel {
             sender_clone.input(AppMsg::MimeSelected(dd.selected()));
         });
 
+        // accept any file dragged onto the window and detect its mime.
+        let drop_target = gtk::DropTarget::new(
+            gtk::gio::File::static_type(),
+            gtk::gdk::DragAction::COPY,
+        );
+        let sender_clone = sender.clone();
+        drop_target.connect_drop(move |_, value, _, _| {
+            if let Ok(file) = value.get::<gtk::gio::File>() {
+                if let Some(path) = file.path() {
+                    sender_clone.input(AppMsg::FileDropped(path));
+                    return true;
+                }
+            }
+            false
+        });
+        window.add_controller(drop_target);
+
+        // the command palette: a Ctrl+P popover fusing mime selection and
+        // the set-default action into one fuzzy-ranked, keyboard-driven list.
+        let palette = gtk::Popover::new();
+        palette.set_parent(&window);
+        palette.set_autohide(true);
+        let palette_vbox = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .build();
+        let palette_search = gtk::SearchEntry::new();
+        palette_search.set_placeholder_text(Some("Type a mime or action..."));
+        let palette_scroll = gtk::ScrolledWindow::builder()
+            .min_content_height(300)
+            .min_content_width(400)
+            .build();
+        let palette_list = gtk::ListBox::new();
+        palette_list.set_selection_mode(gtk::SelectionMode::Single);
+        palette_scroll.set_child(Some(&palette_list));
+        palette_vbox.append(&palette_search);
+        palette_vbox.append(&palette_scroll);
+        palette.set_child(Some(&palette_vbox));
+
+        // a shared helper that maps a palette row back to the existing
+        // message it stands for, then dismisses the palette.
+        let palette_for_activate = palette.clone();
+        let sender_clone = sender.clone();
+        let activate_row = Rc::new(move |row: &gtk::ListBoxRow| {
+            let name = row.widget_name().to_string();
+            if let Some(rest) = name.strip_prefix("mime:") {
+                if let Ok(idx) = rest.parse::<usize>() {
+                    sender_clone.input(AppMsg::PaletteMimeSelected(idx));
+                }
+            } else if let Some(app) = name.strip_prefix("app:") {
+                sender_clone.input(AppMsg::AppSet(app.to_string()));
+            }
+            palette_for_activate.popdown();
+        });
+
+        // keep the palette list in sync with what the user types.
+        let sender_clone = sender.clone();
+        palette_search.connect_changed(move |entry| {
+            sender_clone.input(AppMsg::PaletteSearch(entry.text().to_string()));
+        });
+
+        // clicking / activating a row fires its underlying message.
+        let activate_clone = activate_row.clone();
+        palette_list.connect_row_activated(move |_, row| activate_clone(row));
+
+        // arrow keys move the selection and Enter activates it, so the
+        // palette is fully usable without leaving the search entry.
+        let list_for_keys = palette_list.clone();
+        let activate_clone = activate_row.clone();
+        let key_nav = gtk::EventControllerKey::new();
+        key_nav.connect_key_pressed(move |_, key, _, _| {
+            let selected = list_for_keys.selected_row();
+            match key {
+                gtk::gdk::Key::Down => {
+                    let next = selected
+                        .as_ref()
+                        .and_then(|r| list_for_keys.row_at_index(r.index() + 1))
+                        .or_else(|| list_for_keys.row_at_index(0));
+                    if let Some(row) = next {
+                        list_for_keys.select_row(Some(&row));
+                    }
+                    gtk::glib::Propagation::Stop
+                }
+                gtk::gdk::Key::Up => {
+                    if let Some(row) = selected
+                        .as_ref()
+                        .filter(|r| r.index() > 0)
+                        .and_then(|r| list_for_keys.row_at_index(r.index() - 1))
+                    {
+                        list_for_keys.select_row(Some(&row));
+                    }
+                    gtk::glib::Propagation::Stop
+                }
+                gtk::gdk::Key::Return | gtk::gdk::Key::KP_Enter => {
+                    if let Some(row) = selected {
+                        activate_clone(&row);
+                    }
+                    gtk::glib::Propagation::Stop
+                }
+                _ => gtk::glib::Propagation::Proceed,
+            }
+        });
+        palette_search.add_controller(key_nav);
+
+        // Ctrl+P opens the palette with a cleared query. We dispatch an
+        // empty PaletteSearch unconditionally (the entry's `changed`
+        // signal won't fire when it is already empty) so the palette
+        // repopulates on open without touching the main search state.
+        let palette_for_open = palette.clone();
+        let search_for_open = palette_search.clone();
+        let sender_clone = sender.clone();
+        let open_palette = gtk::EventControllerKey::new();
+        open_palette.connect_key_pressed(move |_, key, _, modifier| {
+            if key == gtk::gdk::Key::p
+                && modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK)
+            {
+                search_for_open.set_text("");
+                sender_clone.input(AppMsg::PaletteSearch(String::new()));
+                palette_for_open.popup();
+                search_for_open.grab_focus();
+                return gtk::glib::Propagation::Stop;
+            }
+            gtk::glib::Propagation::Proceed
+        });
+        window.add_controller(open_palette);
+
         // the outer box for holding all the UI elements
         let main_vbox = gtk::Box::builder()
             .orientation(gtk::Orientation::Vertical)
@@ -159,6 +296,9 @@ impl Component for AppModel {
             label,
             dropdown,
             appbox,
+            palette,
+            palette_search,
+            palette_list,
         };
 
         ComponentParts { model, widgets }
@@ -169,24 +309,40 @@ impl Component for AppModel {
         match msg {
             AppMsg::SearchChanged(text) => {
                 self.mode = AppMode::Searching;
+                self.notice = None;
                 println!("[{:?}] You searched for: {}", self.mode, &text);
                 self.search_str = text;
 
-                let regex =
-                    Regex::new(&format!(r"(?i){}", regex::escape(&self.search_str))).unwrap();
+                if self.search_str.is_empty() {
+                    // an empty query shows the full list, sorted by name
+                    // (mimes_apps is already sorted alphabetically).
+                    let new_list: Vec<&str> =
+                        self.mimes_apps.iter().map(|st| &st.0[..]).collect();
+                    self.current_list = gtk::StringList::new(&new_list);
+                } else {
+                    // fuzzy-match every mime and rank the survivors: higher
+                    // score first, ties broken by shorter mime then name.
+                    let mut scored: Vec<(i32, &str)> = self
+                        .mimes_apps
+                        .iter()
+                        .filter_map(|st| {
+                            fuzzy_score(&self.search_str, &st.0).map(|score| (score, &st.0[..]))
+                        })
+                        .collect();
 
-                // populate the dropdown items with the regex search results
-                let new_list: Vec<&str> = self
-                    .mimes_apps
-                    .iter()
-                    .filter(|st| regex.is_match(&st.0))
-                    .map(|st| &st.0[..])
-                    .collect();
+                    scored.sort_by(|a, b| {
+                        b.0.cmp(&a.0)
+                            .then_with(|| a.1.len().cmp(&b.1.len()))
+                            .then_with(|| a.1.cmp(b.1))
+                    });
 
-                self.current_list = gtk::StringList::new(&new_list);
+                    let new_list: Vec<&str> = scored.into_iter().map(|(_, mime)| mime).collect();
+                    self.current_list = gtk::StringList::new(&new_list);
+                }
             }
             AppMsg::MimeSelected(indx) => {
                 self.mode = AppMode::Selecting;
+                self.notice = None;
                 self.selected_mime_index = indx;
                 if indx == u32::MAX {
                     println!("[{:?}] The search didn't find anything.", self.mode);
@@ -208,6 +364,45 @@ impl Component for AppModel {
                 self.mode = AppMode::Setting;
                 set_default_handler(&self.selected_mime, &app);
             }
+            AppMsg::FileDropped(path) => {
+                // guess the dropped file's mime type and, if we know it,
+                // jump straight to its entry in the dropdown.
+                let mime = guess_mime(&path);
+                println!("[{:?}] Dropped {:?}, guessed mime {:?}", self.mode, path, mime);
+
+                if let Some(indx) = self.mimes_apps.iter().position(|mapp| mapp.0 == mime) {
+                    self.notice = None;
+                    self.mode = AppMode::Selecting;
+                    self.selected_mime = mime;
+                    self.selected_mime_index = indx as u32;
+                    // show the full list so the target index is valid.
+                    let new_list: Vec<&str> =
+                        self.mimes_apps.iter().map(|st| &st.0[..]).collect();
+                    self.current_list = gtk::StringList::new(&new_list);
+                } else {
+                    // detected a type with no registered handler: report it
+                    // so the drop never looks like it did nothing.
+                    self.notice = Some(format!("No application is registered for \"{}\".", mime));
+                }
+            }
+            AppMsg::PaletteSearch(text) => {
+                self.palette_query = text;
+            }
+            AppMsg::PaletteMimeSelected(idx) => {
+                // the palette carries its own index into mimes_apps, so it
+                // never depends on the main dropdown's current_list.
+                if let Some(mapp) = self.mimes_apps.get(idx) {
+                    self.notice = None;
+                    self.mode = AppMode::Selecting;
+                    self.selected_mime = mapp.0.clone();
+                    self.selected_mime_index = idx as u32;
+                    // sync current_list to the full list so the dropdown
+                    // highlights the same entry.
+                    let new_list: Vec<&str> =
+                        self.mimes_apps.iter().map(|st| &st.0[..]).collect();
+                    self.current_list = gtk::StringList::new(&new_list);
+                }
+            }
         }
     }
 
@@ -215,6 +410,65 @@ impl Component for AppModel {
     /// We update the view based on different variants of AppMode.
     /// This avoids an infinite loop as well.
     fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        // refresh the command palette: mime selections plus contextual
+        // set-default actions for the current mime, fuzzy-ranked against
+        // whatever the user has typed into the palette. Only do this while
+        // the palette is actually open so ordinary search keystrokes don't
+        // rebuild hundreds of rows and hit the disk per app.
+        if widgets.palette.is_visible() {
+            while let Some(row) = widgets.palette_list.row_at_index(0) {
+                widgets.palette_list.remove(&row);
+            }
+
+            let query = &self.palette_query;
+            let mut entries: Vec<(i32, String, String)> = Vec::new();
+
+            // contextual actions for the currently selected mime.
+            if !self.selected_mime.is_empty() {
+                if let Some(pos) = self
+                    .mimes_apps
+                    .iter()
+                    .position(|mapp| mapp.0 == self.selected_mime)
+                {
+                    for app in &self.mimes_apps[pos].1 {
+                        let name = resolve_desktop_entry(app.trim())
+                            .map(|e| e.name)
+                            .unwrap_or_else(|| app.clone());
+                        let label =
+                            format!("set {} as default for {}", name, self.selected_mime);
+                        if let Some(score) = fuzzy_score(query, &label) {
+                            entries.push((score, label, format!("app:{}", app)));
+                        }
+                    }
+                }
+            }
+
+            // one entry per mime.
+            for (idx, mapp) in self.mimes_apps.iter().enumerate() {
+                let label = format!("select {}", mapp.0);
+                if let Some(score) = fuzzy_score(query, &label) {
+                    entries.push((score, label, format!("mime:{}", idx)));
+                }
+            }
+
+            entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.len().cmp(&b.1.len())));
+
+            for (_, label, name) in entries {
+                let row = gtk::ListBoxRow::new();
+                row.set_widget_name(&name);
+                let row_label = gtk::Label::new(Some(&label));
+                row_label.set_halign(gtk::Align::Start);
+                row_label.set_margin_all(4);
+                row.set_child(Some(&row_label));
+                widgets.palette_list.append(&row);
+            }
+
+            // preselect the first row so Enter works immediately.
+            if let Some(first) = widgets.palette_list.row_at_index(0) {
+                widgets.palette_list.select_row(Some(&first));
+            }
+        }
+
         // in any mode this state is correct and informative.
         if self.selected_mime_index == u32::MAX {
             widgets
@@ -240,6 +494,12 @@ impl Component for AppModel {
             widgets.dropdown.set_selected(0);
         } else {
             // both other AppModes trigger this part.
+            // when selecting (e.g. after a drag-and-drop) keep the
+            // dropdown in sync so it scrolls to and highlights the entry.
+            if let AppMode::Selecting = self.mode {
+                widgets.dropdown.set_model(Some(&self.current_list));
+                widgets.dropdown.set_selected(self.selected_mime_index);
+            }
             // first, remove all the widgets from appbox
             let children = widgets.appbox.observe_children();
             let nbox = children.n_items();
@@ -290,11 +550,25 @@ impl Component for AppModel {
                         .spacing(10)
                         .build();
 
-                    let app_label = gtk::Label::new(Some(&app));
+                    // resolve the bare desktop id to a friendly name and
+                    // icon, falling back to the filename when unavailable.
+                    let entry = resolve_desktop_entry(app.trim());
+                    let label_text = entry
+                        .as_ref()
+                        .map(|e| e.name.clone())
+                        .unwrap_or_else(|| app.clone());
+                    let icon_name = entry
+                        .as_ref()
+                        .and_then(|e| e.icon.clone())
+                        .unwrap_or_else(|| "application-x-executable".to_string());
+
+                    let app_icon = gtk::Image::from_icon_name(&icon_name);
+                    let app_label = gtk::Label::new(Some(&label_text));
                     app_label.set_hexpand(true); // let the label expand
                     app_label.set_halign(gtk::Align::Start); // align label to start (left)
                     set_default.set_halign(gtk::Align::End); // align button to end (right)
 
+                    app_vbox.append(&app_icon);
                     app_vbox.append(&app_label);
                     app_vbox.append(&set_default);
                     widgets.appbox.append(&app_vbox);
@@ -311,11 +585,206 @@ impl Component for AppModel {
                 });
             }
         }
+
+        // a pending notice (e.g. an unhandled drop) overrides the label.
+        if let Some(notice) = &self.notice {
+            widgets.label.set_label(notice);
+        }
     }
 }
 
 //************************Other Helper Functions***********************
 
+/// A resolved desktop entry: the human-readable bits of a `.desktop`
+/// file that we show in the appbox instead of the bare identifier.
+struct DesktopEntry {
+    name: String,
+    icon: Option<String>,
+    #[allow(dead_code)] // kept for callers that want to launch the app
+    exec: Option<String>,
+}
+
+/// Application directories to search for `.desktop` files, in precedence
+/// order (local dir, then `XDG_DATA_DIRS` with the spec defaults appended).
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = home_dir() {
+        dirs.push(home.join(".local/share/applications"));
+    }
+
+    let mut data_dirs: Vec<String> = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    // make sure the spec-mandated defaults are present.
+    for default in ["/usr/local/share", "/usr/share"] {
+        if !data_dirs.iter().any(|d| d == default) {
+            data_dirs.push(default.to_string());
+        }
+    }
+
+    for dir in data_dirs {
+        dirs.push(PathBuf::from(dir).join("applications"));
+    }
+    dirs
+}
+
+/// Resolves a desktop-entry id to its `Name`, `Icon`, and `Exec`, or
+/// `None` when no matching `.desktop` file is found.
+fn resolve_desktop_entry(app_id: &str) -> Option<DesktopEntry> {
+    let path = application_dirs()
+        .into_iter()
+        .map(|dir| dir.join(app_id))
+        .find(|p| p.exists())?;
+    let contents = fs::read_to_string(path).ok()?;
+
+    let mut name = None;
+    let mut icon = None;
+    let mut exec = None;
+    let mut in_entry = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim() {
+                "Name" if name.is_none() => name = Some(value.trim().to_string()),
+                "Icon" => icon = Some(value.trim().to_string()),
+                "Exec" => exec = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    Some(DesktopEntry {
+        name: name.unwrap_or_else(|| app_id.to_string()),
+        icon,
+        exec,
+    })
+}
+
+/// Scores a candidate against a fuzzy query matched as an in-order,
+/// case-insensitive subsequence; `None` if the query doesn't fit.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    const BASE: i32 = 1;
+    const CONSECUTIVE_BONUS: i32 = 5;
+    const BOUNDARY_BONUS: i32 = 10;
+
+    let cand: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut ci = 0; // current position in the candidate
+    let mut last_matched: Option<usize> = None;
+
+    for qc in query.chars() {
+        let ql = qc.to_ascii_lowercase();
+        let mut matched = false;
+        while ci < cand.len() {
+            if cand[ci].to_ascii_lowercase() == ql {
+                score += BASE;
+                if ci > 0 && last_matched == Some(ci - 1) {
+                    score += CONSECUTIVE_BONUS;
+                }
+                if ci == 0 || matches!(cand[ci - 1], '/' | '-' | '+' | '.') {
+                    score += BOUNDARY_BONUS;
+                }
+                last_matched = Some(ci);
+                ci += 1;
+                matched = true;
+                break;
+            }
+            ci += 1;
+        }
+        if !matched {
+            return None;
+        }
+    }
+
+    Some(score)
+}
+
+/// A stamp identifying the source files a cache was built from: each
+/// source path paired with its modification time (seconds since the
+/// epoch). A cache is only reused when this matches the sources exactly.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq)]
+struct CacheStamp {
+    sources: Vec<(String, u64)>,
+}
+
+/// The on-disk parse cache: the parsed mime/app table plus the stamp of
+/// the files it came from.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MimeCache {
+    stamp: CacheStamp,
+    data: Vec<(String, Vec<String>)>,
+}
+
+/// The path of the on-disk parse cache, under `$XDG_CACHE_HOME` (or
+/// `~/.cache`). Returns `None` when neither location can be determined.
+fn cache_path() -> Option<PathBuf> {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| home_dir().map(|h| h.join(".cache")))?;
+    Some(base.join("xdg-mimer").join("cache.bin"))
+}
+
+/// Builds a stamp from the current modification times of the source files.
+fn source_stamp(paths: &[PathBuf]) -> CacheStamp {
+    let sources = paths
+        .iter()
+        .filter_map(|p| {
+            let mtime = fs::metadata(p)
+                .ok()?
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            Some((p.to_string_lossy().to_string(), mtime))
+        })
+        .collect();
+    CacheStamp { sources }
+}
+
+/// Like [`get_mimes_apps`] but reuses an on-disk cache when the source
+/// files' mtimes still match the stamp, re-parsing and rewriting otherwise.
+fn get_mimes_apps_cached(filenames: Vec<PathBuf>) -> Vec<(String, Vec<String>)> {
+    let stamp = source_stamp(&filenames);
+
+    if let Some(path) = cache_path() {
+        if let Ok(bytes) = fs::read(&path) {
+            if let Ok(cache) = bincode::deserialize::<MimeCache>(&bytes) {
+                if cache.stamp == stamp {
+                    return cache.data;
+                }
+            }
+        }
+    }
+
+    let data = get_mimes_apps(filenames);
+
+    if let Some(path) = cache_path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(bytes) = bincode::serialize(&MimeCache {
+            stamp,
+            data: data.clone(),
+        }) {
+            let _ = fs::write(&path, bytes);
+        }
+    }
+
+    data
+}
+
 /// Gets and stores all mimes and their available apps from a vector of filepaths
 fn get_mimes_apps<T: AsRef<Path>>(filenames: Vec<T>) -> Vec<(String, Vec<String>)> {
     // we use HashMap to efficiently update vector of apps for a mime
@@ -355,27 +824,180 @@ fn get_mimes_apps<T: AsRef<Path>>(filenames: Vec<T>) -> Vec<(String, Vec<String>
     mime_apps
 }
 
-/// Gets the default app for a given mime
+/// Guesses a file's mime type by sniffing magic bytes, then by extension,
+/// falling back to `application/octet-stream`.
+fn guess_mime(path: &Path) -> String {
+    // content sniff: read only a bounded header so dropping a huge file
+    // (a multi-GB video or ISO) doesn't pull the whole thing into memory.
+    if let Ok(mut file) = fs::File::open(path) {
+        use std::io::Read;
+        let mut head = [0u8; 16];
+        let n = file.read(&mut head).unwrap_or(0);
+        let head = &head[..n];
+        if head.starts_with(&[0x89, b'P', b'N', b'G']) {
+            return "image/png".to_string();
+        }
+        if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return "image/jpeg".to_string();
+        }
+        if head.starts_with(b"GIF8") {
+            return "image/gif".to_string();
+        }
+        if head.starts_with(b"%PDF") {
+            return "application/pdf".to_string();
+        }
+        if head.starts_with(b"\x7FELF") {
+            return "application/x-executable".to_string();
+        }
+        if head.starts_with(b"PK\x03\x04") {
+            return "application/zip".to_string();
+        }
+    }
+
+    // extension fallback.
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("txt") => "text/plain",
+        Some("html") | Some("htm") => "text/html",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("mp3") => "audio/mpeg",
+        Some("mp4") => "video/mp4",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// The user-level `mimeapps.list` files, in precedence order, as defined
+/// by the XDG base and mime-apps specs.
+fn user_mimeapps_lists() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(home) = home_dir() {
+        paths.push(home.join(".config/mimeapps.list"));
+        paths.push(home.join(".local/share/applications/mimeapps.list"));
+    }
+    paths
+}
+
+/// Gets the default app for a given mime by reading the
+/// `[Default Applications]` group of the user `mimeapps.list` files in
+/// precedence order and returning the first association found. Returns an
+/// empty string when no default is set, mirroring the old behavior.
 fn get_default_handler(mime_type: &str) -> String {
-    // we want it to output the default app if there
-    // is one set, otherwise give an empty string for
-    // all other cases.
-    let output = Command::new("xdg-mime")
-        .args(["query", "default", mime_type])
-        .output()
-        .ok()
-        .and_then(|o| String::from_utf8(o.stdout).ok())
-        .map(|s| s.trim().to_string())
-        .unwrap_or_default();
+    for path in user_mimeapps_lists() {
+        let contents = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        if let Some(value) = read_group_value(&contents, "[Default Applications]", mime_type) {
+            // a mime may list several apps separated by ';'; the first
+            // one is the default.
+            if let Some(app) = value.split(';').find(|s| !s.trim().is_empty()) {
+                return app.trim().to_string();
+            }
+        }
+    }
+    String::new()
+}
 
-    output
+/// Returns the raw value for `key` inside `group` of an INI-style
+/// `mimeapps.list`, or `None` when the group or key is absent.
+fn read_group_value(contents: &str, group: &str, key: &str) -> Option<String> {
+    let mut in_group = false;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_group = trimmed == group;
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        if let Some((k, v)) = trimmed.split_once('=') {
+            if k.trim() == key {
+                return Some(v.trim().to_string());
+            }
+        }
+    }
+    None
 }
 
-/// Sets the default app for a given mime
+/// Sets the default app for a given mime by upserting the
+/// `mime=handler;` line under `[Default Applications]` in the user-level
+/// `~/.config/mimeapps.list`, creating the group or file if needed, and
+/// writing it back atomically via a temp file + rename.
 fn set_default_handler(mime_type: &str, handler: &str) {
-    let _ = Command::new("xdg-mime")
-        .args(["default", handler, mime_type])
-        .status();
+    let path = match home_dir() {
+        Some(home) => home.join(".config/mimeapps.list"),
+        None => return,
+    };
+
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    let new_contents = upsert_default(&contents, mime_type, handler);
+
+    // make sure the parent directory exists, then write atomically.
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("list.tmp");
+    if fs::write(&tmp, new_contents).is_ok() {
+        let _ = fs::rename(&tmp, &path);
+    }
+}
+
+/// Produces the contents of a `mimeapps.list` with `mime=handler;` set
+/// under `[Default Applications]`, replacing any existing line for the
+/// mime and creating the group when it does not yet exist.
+fn upsert_default(contents: &str, mime_type: &str, handler: &str) -> String {
+    let new_line = format!("{}={};", mime_type, handler);
+    let mut lines: Vec<String> = contents.lines().map(|l| l.to_string()).collect();
+
+    let mut group_start = None;
+    let mut group_end = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed == "[Default Applications]" {
+            group_start = Some(i);
+        } else if group_start.is_some() && trimmed.starts_with('[') && trimmed.ends_with(']') {
+            group_end = i;
+            break;
+        }
+    }
+
+    match group_start {
+        Some(start) => {
+            // look for an existing line for this mime within the group.
+            let existing = (start + 1..group_end).find(|&i| {
+                lines[i]
+                    .trim()
+                    .split_once('=')
+                    .is_some_and(|(k, _)| k.trim() == mime_type)
+            });
+            match existing {
+                Some(i) => lines[i] = new_line,
+                None => lines.insert(group_end, new_line),
+            }
+        }
+        None => {
+            if !lines.is_empty() && !lines.last().map(|l| l.is_empty()).unwrap_or(true) {
+                lines.push(String::new());
+            }
+            lines.push("[Default Applications]".to_string());
+            lines.push(new_line);
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
 }
 
 //*********************************************************************